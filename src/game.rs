@@ -1,23 +1,72 @@
+use std::{collections::HashMap, time::Duration};
+
 use bevy::{
     color::palettes::{
-        css::{GREEN, RED, WHITE},
-        tailwind::{RED_200, RED_400, RED_600, RED_800, RED_900, SKY_300, SKY_400},
+        css::{BLACK, BLUE, GRAY, GREEN, MAROON, NAVY, RED, TEAL, WHITE},
+        tailwind::{AMBER_400, RED_200, RED_400, RED_600, RED_800, RED_900, SKY_300, SKY_400},
     },
     core_pipeline::bloom::Bloom,
     ecs::{query::QueryData, system::SystemParam},
     input::common_conditions::input_just_released,
     prelude::*,
+    time::Stopwatch,
+    window::PrimaryWindow,
 };
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 
-const X: i32 = 30;
-const Y: i32 = 16;
-const BOMBS: i32 = 70;
 const UNIT: f32 = 48.0;
 const GAP: f32 = 2.0;
 const PADDING: f32 = 24.0;
-pub const SCREEN_WIDTH: f32 = X as f32 * UNIT + (X - 1) as f32 * GAP + PADDING * 2.0;
-pub const SCREEN_HEIGHT: f32 = Y as f32 * UNIT + (Y - 1) as f32 * GAP + PADDING * 2.0;
+
+/// Board size and bomb count of a game, chosen on `OnEnter(GameState::Prepare)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Resource)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    #[default]
+    Expert,
+    Custom {
+        columns: i32,
+        rows: i32,
+        bombs: i32,
+    },
+}
+
+impl Difficulty {
+    pub fn columns(&self) -> i32 {
+        match self {
+            Difficulty::Beginner => 9,
+            Difficulty::Intermediate => 16,
+            Difficulty::Expert => 30,
+            Difficulty::Custom { columns, .. } => *columns,
+        }
+    }
+
+    pub fn rows(&self) -> i32 {
+        match self {
+            Difficulty::Beginner => 9,
+            Difficulty::Intermediate => 16,
+            Difficulty::Expert => 16,
+            Difficulty::Custom { rows, .. } => *rows,
+        }
+    }
+
+    pub fn bombs(&self) -> i32 {
+        match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Intermediate => 40,
+            Difficulty::Expert => 99,
+            Difficulty::Custom { bombs, .. } => *bombs,
+        }
+    }
+}
+
+/// Window size needed to fit a `columns` x `rows` board.
+pub fn screen_size(columns: i32, rows: i32) -> (f32, f32) {
+    let width = columns as f32 * UNIT + (columns - 1) as f32 * GAP + PADDING * 2.0;
+    let height = rows as f32 * UNIT + (rows - 1) as f32 * GAP + PADDING * 2.0;
+    (width, height)
+}
 
 #[derive(Clone, Debug, Resource)]
 pub struct Materials {
@@ -27,7 +76,13 @@ pub struct Materials {
     // TODO: replace these materials to give better visual
     flagged: Handle<ColorMaterial>,
     bomb: Handle<ColorMaterial>,
-    count: [Handle<ColorMaterial>; 8],
+    // indexed by adjacent-bomb count, 0 through 8 (a cell can have up to 8
+    // neighbors), so this must stay one longer than `digits`
+    count: [Handle<ColorMaterial>; 9],
+    hint: Handle<ColorMaterial>,
+
+    // one text color per adjacent-bomb count, 1 through 8, classic palette
+    digits: [Color; 8],
 }
 
 impl FromWorld for Materials {
@@ -46,6 +101,18 @@ impl FromWorld for Materials {
             mats.add(Color::from(RED_900)),
             mats.add(Color::from(RED_900)),
             mats.add(Color::from(RED_900)),
+            mats.add(Color::from(RED_900)),
+        ];
+        let hint = mats.add(Color::from(AMBER_400));
+        let digits = [
+            Color::from(BLUE),
+            Color::from(GREEN),
+            Color::from(RED),
+            Color::from(NAVY),
+            Color::from(MAROON),
+            Color::from(TEAL),
+            Color::from(BLACK),
+            Color::from(GRAY),
         ];
         Self {
             covered,
@@ -53,6 +120,8 @@ impl FromWorld for Materials {
             flagged,
             bomb,
             count,
+            hint,
+            digits,
         }
     }
 }
@@ -62,13 +131,22 @@ pub enum GameState {
     #[default]
     Prepare,
     Running,
-    Over,
+    Won,
+    Lost,
 }
 
 impl GameState {
     pub fn is_running(&self) -> bool {
         matches!(self, GameState::Running)
     }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self, GameState::Won | GameState::Lost)
+    }
+}
+
+fn game_over(state: Res<State<GameState>>) -> bool {
+    state.is_over()
 }
 
 // a covered cell can be uncovered or flagged
@@ -78,6 +156,11 @@ pub struct Covered;
 #[derive(Clone, Copy, Debug, Component)]
 pub struct Flagged;
 
+// the adjacent-bomb count shown on a revealed, non-bomb cell; lets the
+// solver read what's on screen without peeking at `Cell::is_bomb`
+#[derive(Clone, Copy, Debug, Component)]
+pub struct Count(u8);
+
 #[derive(Clone, Copy, Debug, Component)]
 #[require(Transform, Visibility)]
 pub struct Cell {
@@ -86,32 +169,28 @@ pub struct Cell {
     is_bomb: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Resource)]
 pub struct Board {
     columns: i32,
     rows: i32,
-    _bombs: i32,
+    bombs: i32,
     grids: Vec<Cell>,
 }
 
 impl Board {
     pub fn new(columns: i32, rows: i32, bombs: i32) -> Self {
-        let mut rng = rand::rng();
-        let mut grids: Vec<bool> = (0..(columns * rows)).map(|idx| idx < bombs).collect();
-        grids.shuffle(&mut rng);
-        let grids = grids
-            .iter()
-            .enumerate()
-            .map(|(idx, &is_bomb)| Cell {
-                x: idx as i32 % columns,
-                y: idx as i32 / columns,
-                is_bomb,
+        // bombs are placed later, on the first uncover, so every cell starts safe
+        let grids = (0..(columns * rows))
+            .map(|idx| Cell {
+                x: idx % columns,
+                y: idx / columns,
+                is_bomb: false,
             })
             .collect();
         Self {
             columns,
             rows,
-            _bombs: bombs,
+            bombs,
             grids,
         }
     }
@@ -119,6 +198,182 @@ impl Board {
     pub fn iter(&self) -> impl Iterator<Item = Cell> {
         self.grids.iter().copied()
     }
+
+    pub fn bombs(&self) -> i32 {
+        self.bombs
+    }
+}
+
+// tracks whether `Board::bombs` have already been scattered across the grid;
+// reset every time we enter `GameState::Prepare`: `false` for a fresh board,
+// `true` for a restored save that already holds bombs
+#[derive(Clone, Copy, Debug, Default, Resource)]
+struct BombsPlaced(bool);
+
+// counts up from the first uncover until the game is `Won` or `Lost`
+#[derive(Clone, Debug, Default, Resource)]
+struct ElapsedTime(Stopwatch);
+
+// best completion time per `Difficulty`, persisted to `SCOREBOARD_PATH`
+#[derive(Clone, Debug, Default, Resource)]
+struct Scoreboard(HashMap<Difficulty, f32>);
+
+const SCOREBOARD_PATH: &str = "minesweeper.scores";
+
+// a stable, human-readable key for a `Difficulty`, one variant per line of
+// `SCOREBOARD_PATH`
+fn encode_difficulty(difficulty: Difficulty) -> String {
+    match difficulty {
+        Difficulty::Beginner => "beginner".to_string(),
+        Difficulty::Intermediate => "intermediate".to_string(),
+        Difficulty::Expert => "expert".to_string(),
+        Difficulty::Custom {
+            columns,
+            rows,
+            bombs,
+        } => format!("custom:{columns}:{rows}:{bombs}"),
+    }
+}
+
+fn decode_difficulty(key: &str) -> Option<Difficulty> {
+    match key {
+        "beginner" => Some(Difficulty::Beginner),
+        "intermediate" => Some(Difficulty::Intermediate),
+        "expert" => Some(Difficulty::Expert),
+        _ => {
+            let mut fields = key.strip_prefix("custom:")?.split(':');
+            let columns = fields.next()?.parse().ok()?;
+            let rows = fields.next()?.parse().ok()?;
+            let bombs = fields.next()?.parse().ok()?;
+            Some(Difficulty::Custom {
+                columns,
+                rows,
+                bombs,
+            })
+        }
+    }
+}
+
+// one `<difficulty>,<seconds>` entry per line
+fn encode_scoreboard(scoreboard: &Scoreboard) -> String {
+    scoreboard
+        .0
+        .iter()
+        .map(|(&difficulty, seconds)| format!("{},{seconds}", encode_difficulty(difficulty)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn decode_scoreboard(content: &str) -> Scoreboard {
+    let mut scoreboard = HashMap::new();
+    for line in content.lines() {
+        let Some((key, seconds)) = line.split_once(',') else {
+            continue;
+        };
+        let Some(difficulty) = decode_difficulty(key) else {
+            continue;
+        };
+        let Ok(seconds) = seconds.parse() else {
+            continue;
+        };
+        scoreboard.insert(difficulty, seconds);
+    }
+    Scoreboard(scoreboard)
+}
+
+const SAVE_PATH: &str = "minesweeper.save";
+
+// raw contents of `SAVE_PATH`, loaded once at `Startup` and consumed by the
+// first `prepare`; `None` means either there was no save or it got used up
+#[derive(Clone, Debug, Default, Resource)]
+struct SavedGame(Option<String>);
+
+// a cell's save state fits in 3 bits: is it a bomb, is it covered, is it flagged
+fn cell_state(is_bomb: bool, covered: bool, flagged: bool) -> u8 {
+    is_bomb as u8 | (covered as u8) << 1 | (flagged as u8) << 2
+}
+
+// obfuscate the state with a per-cell offset so the save file isn't a plain
+// readout of the board; shifting by `idx` also means identical cells at
+// different positions don't encode to the same char
+fn encode_cell(state: u8, idx: usize) -> char {
+    let shifted = (state as usize + idx) % 26;
+    (b'A' + shifted as u8) as char
+}
+
+fn decode_cell(ch: char, idx: usize) -> Option<u8> {
+    let code = ch as i32 - 'A' as i32;
+    if !(0..26).contains(&code) {
+        return None;
+    }
+    let state = (code - (idx % 26) as i32).rem_euclid(26) as u8;
+    (state < 8).then_some(state)
+}
+
+// `columns,rows,bombs` header followed by one encoded char per cell, in the
+// same row-major order as `Board::grids`
+fn encode_save(board: &Board, states: &[(bool, bool)]) -> String {
+    let grid: String = board
+        .grids
+        .iter()
+        .zip(states)
+        .enumerate()
+        .map(|(idx, (cell, &(covered, flagged)))| {
+            encode_cell(cell_state(cell.is_bomb, covered, flagged), idx)
+        })
+        .collect();
+    format!("{},{},{}\n{}", board.columns, board.rows, board.bombs, grid)
+}
+
+fn decode_save(content: &str) -> Option<(Board, Vec<(bool, bool)>)> {
+    let (header, grid) = content.split_once('\n')?;
+    let mut fields = header.split(',');
+    let columns: i32 = fields.next()?.parse().ok()?;
+    let rows: i32 = fields.next()?.parse().ok()?;
+    let bombs: i32 = fields.next()?.parse().ok()?;
+    if grid.chars().count() != (columns * rows) as usize {
+        return None;
+    }
+
+    let mut grids = Vec::with_capacity(grid.len());
+    let mut states = Vec::with_capacity(grid.len());
+    for (idx, ch) in grid.chars().enumerate() {
+        let state = decode_cell(ch, idx)?;
+        grids.push(Cell {
+            x: idx as i32 % columns,
+            y: idx as i32 / columns,
+            is_bomb: state & 0b001 != 0,
+        });
+        states.push((state & 0b010 != 0, state & 0b100 != 0));
+    }
+
+    Some((
+        Board {
+            columns,
+            rows,
+            bombs,
+            grids,
+        },
+        states,
+    ))
+}
+
+// true if (ax, ay) is one of the 8 neighbors of (bx, by); the single place
+// every adjacency check in this file defers to, so a future change to what
+// "adjacent" means (e.g. a non-8-connected board) only needs fixing here
+fn is_adjacent(ax: i32, ay: i32, bx: i32, by: i32) -> bool {
+    ax - 1 <= bx && bx <= ax + 1 && ay - 1 <= by && by <= ay + 1 && !(ax == bx && ay == by)
+}
+
+// the number of bombs adjacent to (x, y), computed straight from the board
+// layout rather than the ECS world, since cells haven't been spawned yet
+fn adjacent_bomb_count(board: &Board, x: i32, y: i32) -> usize {
+    board
+        .grids
+        .iter()
+        .filter(|cell| is_adjacent(x, y, cell.x, cell.y))
+        .filter(|cell| cell.is_bomb)
+        .count()
 }
 
 #[derive(QueryData)]
@@ -136,19 +391,75 @@ pub struct InterationParam<'w, 's> {
     query: Query<'w, 's, BoardQuery>,
     command: Commands<'w, 's>,
     materials: Res<'w, Materials>,
+    board: Res<'w, Board>,
+    bombs_placed: ResMut<'w, BombsPlaced>,
+    elapsed: ResMut<'w, ElapsedTime>,
+}
+
+// picks up to `bombs` distinct entities from `candidates` (entity, x, y) to
+// scatter bombs on, excluding `clicked` and its 8 neighbors so the opening
+// click is always safe; a partial Fisher-Yates shuffles only the prefix we
+// actually need, pulled out as a free function so it's testable without a
+// live `World`
+fn select_bomb_entities(
+    candidates: &[(Entity, i32, i32)],
+    clicked: (i32, i32),
+    bombs: usize,
+) -> Vec<Entity> {
+    let (x, y) = clicked;
+    let mut eligible: Vec<Entity> = candidates
+        .iter()
+        .filter(|&&(_, cx, cy)| !is_adjacent(x, y, cx, cy) && !(cx == x && cy == y))
+        .map(|&(entity, _, _)| entity)
+        .collect();
+
+    let bombs = bombs.min(eligible.len());
+    let mut rng = rand::rng();
+    let len = eligible.len();
+    for i in 0..bombs {
+        let j = rng.random_range(i..len);
+        eligible.swap(i, j);
+    }
+    eligible.truncate(bombs);
+    eligible
 }
 
 impl InterationParam<'_, '_> {
+    // on the very first uncover, scatter `Board::bombs` bombs across every cell
+    // except the clicked one and its 8 neighbors, guaranteeing a safe opening
+    fn ensure_bombs_placed(&mut self, target: Entity) -> Result<()> {
+        if self.bombs_placed.0 {
+            return Ok(());
+        }
+        let clicked = self.query.get(target)?;
+        let (x, y) = (clicked.cell.x, clicked.cell.y);
+
+        let candidates: Vec<(Entity, i32, i32)> = self
+            .query
+            .iter()
+            .map(|ent| (ent.entity, ent.cell.x, ent.cell.y))
+            .collect();
+        let bombs = select_bomb_entities(&candidates, (x, y), self.board.bombs() as usize);
+
+        for entity in bombs {
+            let cell = self.query.get(entity)?.cell;
+            self.command.entity(entity).insert(Cell {
+                is_bomb: true,
+                ..*cell
+            });
+        }
+
+        self.bombs_placed.0 = true;
+        self.elapsed.0.unpause();
+        Ok(())
+    }
+
     fn count_adjacents(&self, target: Entity) -> Result<(Vec<Entity>, usize, usize)> {
         let target = self.query.get(target)?;
-        let adjacents = self.query.iter().filter(|ent| {
-            // keep only the adjacent ones
-            target.cell.x - 1 <= ent.cell.x
-                && ent.cell.x <= target.cell.x + 1
-                && target.cell.y - 1 <= ent.cell.y
-                && ent.cell.y <= target.cell.y + 1
-                && !(ent.cell.x == target.cell.x && ent.cell.y == target.cell.y)
-        });
+        let adjacents = self
+            .query
+            .iter()
+            .filter(|ent| is_adjacent(target.cell.x, target.cell.y, ent.cell.x, ent.cell.y));
         let cnt_bombs = adjacents.clone().filter(|ent| ent.cell.is_bomb).count();
         let cnt_flagged = adjacents
             .clone()
@@ -184,6 +495,9 @@ impl InterationParam<'_, '_> {
     }
 
     fn uncover(&mut self, target: Entity) {
+        if self.ensure_bombs_placed(target).is_err() {
+            return;
+        }
         let Ok((adjacents, cnt_bombs, cnt_flagged)) = self.count_adjacents(target) else {
             return;
         };
@@ -227,16 +541,361 @@ impl InterationParam<'_, '_> {
         }
         // change the material depending on bomb count
         ent.material.0 = self.materials.count[cnt_bombs].clone();
+        self.command.entity(target).insert(Count(cnt_bombs as u8));
         if cnt_bombs == 0 {
             // if there are no bomb in adjacent cells, recursively uncover them
             for ent in adjacents {
                 self.command.entity(ent).remove::<Covered>();
             }
+        } else {
+            spawn_digit(&mut self.command.entity(target), cnt_bombs, &self.materials);
         }
         false
     }
 }
 
+// label a revealed cell with its adjacent-bomb count, as a Text2d child so
+// despawning the cell (see `cleanup`) despawns the label along with it
+fn spawn_digit(entity: &mut EntityCommands, count: usize, materials: &Materials) {
+    entity.with_children(|parent| {
+        parent.spawn((
+            Text2d::new(count.to_string()),
+            TextFont {
+                font_size: UNIT * 0.6,
+                ..Default::default()
+            },
+            TextColor(materials.digits[count - 1]),
+            Transform::from_xyz(0.0, 0.0, 1.0),
+        ));
+    });
+}
+
+// cap on how much backtracking work a single probability estimate may do,
+// split evenly across `SOLVER_TRIALS` independently reshuffled runs
+const SOLVER_NODE_BUDGET: usize = 200_000;
+
+// number of independent, freshly reshuffled backtrack runs `solve` pools
+// together; a single fixed order can only explore one DFS path through a
+// budget-limited search, which is *not* a random sample, so we resample by
+// restarting with a new random order instead
+const SOLVER_TRIALS: usize = 20;
+
+#[derive(QueryData)]
+pub struct SolverQuery {
+    entity: Entity,
+    cell: &'static Cell,
+    covered: Option<&'static Covered>,
+    flagged: Option<&'static Flagged>,
+    count: Option<&'static Count>,
+}
+
+// a revealed cell's remaining constraint: `required` bombs must be hiding
+// among the covered, unflagged cells listed in `cells` (indices into the
+// frontier vector built by `SolverParam::probabilities`)
+struct Constraint {
+    cells: Vec<usize>,
+    required: i32,
+}
+
+#[derive(SystemParam)]
+pub struct SolverParam<'w, 's> {
+    query: Query<'w, 's, SolverQuery>,
+    board: Res<'w, Board>,
+}
+
+impl SolverParam<'_, '_> {
+    // bomb probability in [0, 1] for every covered, unflagged cell, estimated
+    // from revealed counts only - a covered cell's `Cell::is_bomb` is never read
+    pub fn probabilities(&self) -> HashMap<Entity, f64> {
+        let covered: Vec<_> = self
+            .query
+            .iter()
+            .filter(|ent| ent.covered.is_some() && ent.flagged.is_none())
+            .collect();
+
+        let mut frontier = Vec::new();
+        let mut frontier_index = HashMap::new();
+        let mut constraints = Vec::new();
+        for revealed in self.query.iter().filter(|ent| ent.count.is_some()) {
+            let (rx, ry) = (revealed.cell.x, revealed.cell.y);
+
+            let neighbors: Vec<Entity> = covered
+                .iter()
+                .filter(|ent| is_adjacent(rx, ry, ent.cell.x, ent.cell.y))
+                .map(|ent| ent.entity)
+                .collect();
+            if neighbors.is_empty() {
+                continue;
+            }
+            let flagged_neighbors = self
+                .query
+                .iter()
+                .filter(|ent| ent.flagged.is_some() && is_adjacent(rx, ry, ent.cell.x, ent.cell.y))
+                .count();
+
+            let cells = neighbors
+                .into_iter()
+                .map(|entity| {
+                    *frontier_index.entry(entity).or_insert_with(|| {
+                        frontier.push(entity);
+                        frontier.len() - 1
+                    })
+                })
+                .collect();
+            constraints.push(Constraint {
+                cells,
+                required: revealed.count.unwrap().0 as i32 - flagged_neighbors as i32,
+            });
+        }
+
+        let other_count = covered.len() - frontier.len();
+        let flagged_total = self
+            .query
+            .iter()
+            .filter(|ent| ent.flagged.is_some())
+            .count() as i32;
+        let remaining_bombs = self.board.bombs() - flagged_total;
+
+        let (frontier_probs, other_prob) =
+            solve(&frontier, &constraints, other_count, remaining_bombs);
+
+        covered
+            .iter()
+            .map(|ent| {
+                let probability = frontier_index
+                    .get(&ent.entity)
+                    .map_or(other_prob, |&idx| frontier_probs[idx]);
+                (ent.entity, probability)
+            })
+            .collect()
+    }
+
+    // the covered, unflagged cell least likely to be a bomb
+    pub fn safest_cell(&self) -> Option<Entity> {
+        self.probabilities()
+            .into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+    }
+}
+
+// Monte-Carlo estimate of per-cell bomb probability: pool `SOLVER_TRIALS`
+// independent runs, each reshuffling the frontier order and backtracking
+// over 0/1 assignments that satisfy every constraint and the remaining bomb
+// budget, and tally how often each cell came up a bomb across every run
+fn solve(
+    frontier: &[Entity],
+    constraints: &[Constraint],
+    other_count: usize,
+    remaining_bombs: i32,
+) -> (Vec<f64>, f64) {
+    let board_density = || {
+        let total = frontier.len() + other_count;
+        if total == 0 {
+            0.0
+        } else {
+            (remaining_bombs.max(0) as f64 / total as f64).clamp(0.0, 1.0)
+        }
+    };
+
+    if frontier.is_empty() {
+        return (Vec::new(), board_density());
+    }
+
+    let trial_budget = (SOLVER_NODE_BUDGET / SOLVER_TRIALS).max(1);
+    let mut tally = vec![0usize; frontier.len()];
+    let mut other_bomb_tally = 0usize;
+    let mut valid = 0usize;
+    let mut completed_trials = 0usize;
+    let mut rng = rand::rng();
+
+    for _ in 0..SOLVER_TRIALS {
+        let mut order: Vec<usize> = (0..frontier.len()).collect();
+        order.shuffle(&mut rng);
+
+        let mut assigned = vec![false; frontier.len()];
+        let mut values = vec![false; frontier.len()];
+        let mut budget = trial_budget;
+
+        backtrack(
+            0,
+            &order,
+            &mut assigned,
+            &mut values,
+            constraints,
+            other_count,
+            remaining_bombs,
+            &mut tally,
+            &mut other_bomb_tally,
+            &mut valid,
+            &mut budget,
+        );
+
+        if budget > 0 {
+            // this run enumerated its whole search tree rather than being
+            // cut off partway through, so it isn't order-biased
+            completed_trials += 1;
+        }
+    }
+
+    if completed_trials == 0 || valid == 0 {
+        // every trial was truncated before finishing (or none found a
+        // consistent assignment): trusting the tally here would reflect
+        // whichever prefix of each random order happened to be explored,
+        // not an actual sample, so fall back to the non-misleading
+        // board-wide bomb density instead
+        let probability = board_density();
+        return (vec![probability; frontier.len()], probability);
+    }
+
+    let frontier_probs = tally.iter().map(|&c| c as f64 / valid as f64).collect();
+    let other_prob = if other_count == 0 {
+        0.0
+    } else {
+        other_bomb_tally as f64 / (valid * other_count) as f64
+    };
+    (frontier_probs, other_prob)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn backtrack(
+    idx: usize,
+    order: &[usize],
+    assigned: &mut [bool],
+    values: &mut [bool],
+    constraints: &[Constraint],
+    other_count: usize,
+    remaining_bombs: i32,
+    tally: &mut [usize],
+    other_bomb_tally: &mut usize,
+    valid: &mut usize,
+    budget: &mut usize,
+) {
+    if *budget == 0 {
+        return;
+    }
+    *budget -= 1;
+
+    if idx == order.len() {
+        let assigned_bombs = values.iter().filter(|&&bomb| bomb).count() as i32;
+        let remaining_for_other = remaining_bombs - assigned_bombs;
+        if remaining_for_other < 0 || remaining_for_other as usize > other_count {
+            return;
+        }
+        *valid += 1;
+        for (cell, &bomb) in values.iter().enumerate() {
+            if bomb {
+                tally[cell] += 1;
+            }
+        }
+        *other_bomb_tally += remaining_for_other as usize;
+        return;
+    }
+
+    let cell = order[idx];
+    for value in [false, true] {
+        values[cell] = value;
+        assigned[cell] = true;
+        if consistent(constraints, assigned, values) {
+            backtrack(
+                idx + 1,
+                order,
+                assigned,
+                values,
+                constraints,
+                other_count,
+                remaining_bombs,
+                tally,
+                other_bomb_tally,
+                valid,
+                budget,
+            );
+        }
+        assigned[cell] = false;
+        if *budget == 0 {
+            return;
+        }
+    }
+}
+
+// true as long as no constraint is already violated or unsatisfiable, given
+// only the cells assigned so far
+fn consistent(constraints: &[Constraint], assigned: &[bool], values: &[bool]) -> bool {
+    constraints.iter().all(|constraint| {
+        let mut sum = 0;
+        let mut unresolved = 0;
+        for &cell in &constraint.cells {
+            if assigned[cell] {
+                sum += values[cell] as i32;
+            } else {
+                unresolved += 1;
+            }
+        }
+        sum <= constraint.required && sum + unresolved >= constraint.required
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+struct AutoPlay(bool);
+
+// throttles how often `autoplay` re-solves the board; `solve` can burn up to
+// `SOLVER_NODE_BUDGET` backtracking nodes per call, so re-running it every
+// frame would stutter the app for as long as auto-play stays on
+#[derive(Resource)]
+struct AutoPlayTimer(Timer);
+
+impl Default for AutoPlayTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.25, TimerMode::Repeating))
+    }
+}
+
+fn hint(
+    solver: SolverParam,
+    mut query: Query<&mut MeshMaterial2d<ColorMaterial>, (With<Covered>, Without<Flagged>)>,
+    materials: Res<Materials>,
+) {
+    let Some(entity) = solver.safest_cell() else {
+        return;
+    };
+    if let Ok(mut material) = query.get_mut(entity) {
+        material.0 = materials.hint.clone();
+    }
+}
+
+fn toggle_autoplay(mut autoplay: ResMut<AutoPlay>) {
+    autoplay.0 = !autoplay.0;
+}
+
+fn autoplay(
+    autoplay: Res<AutoPlay>,
+    time: Res<Time>,
+    mut timer: ResMut<AutoPlayTimer>,
+    solver: SolverParam,
+    mut interation: InterationParam,
+) {
+    if !autoplay.0 {
+        return;
+    }
+    if !timer.0.tick(time.delta()).just_finished() {
+        // re-solving is expensive, so only act once per tick of the throttle
+        return;
+    }
+    if let Some(entity) = solver.safest_cell() {
+        interation.uncover(entity);
+    }
+}
+
+// markers for the UI text entities so their update systems can find them
+#[derive(Clone, Copy, Debug, Component)]
+struct TimerText;
+
+#[derive(Clone, Copy, Debug, Component)]
+struct BestTimeText;
+
+#[derive(Clone, Copy, Debug, Component)]
+struct Banner;
+
 fn setup(mut command: Commands, mut state: ResMut<NextState<GameState>>) {
     command.spawn((
         Camera2d,
@@ -249,6 +908,27 @@ fn setup(mut command: Commands, mut state: ResMut<NextState<GameState>>) {
         Bloom::NATURAL,
     ));
 
+    command.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..Default::default()
+        },
+        TimerText,
+    ));
+    command.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..Default::default()
+        },
+        BestTimeText,
+    ));
+
     state.set(GameState::Running);
 }
 
@@ -258,8 +938,7 @@ fn success(query: Query<&Cell, With<Covered>>, mut state: ResMut<NextState<GameS
     if count > 0 && success {
         // sometimes this system may query no cell at all, so we check if count is correct
         // if all covered cells are bombs, then the player have won
-        // I don't care enough to separate win & lose
-        state.set(GameState::Over);
+        state.set(GameState::Won);
     }
 }
 
@@ -267,36 +946,232 @@ fn restart(mut state: ResMut<NextState<GameState>>) {
     state.set(GameState::Prepare);
 }
 
+// cycles through the named presets; `Custom` has no keyboard-reachable way
+// to pick its dimensions/bomb count, so cycling through it would just get
+// stuck, and it falls back to `Beginner` instead
+fn cycle_difficulty(mut difficulty: ResMut<Difficulty>) {
+    *difficulty = match *difficulty {
+        Difficulty::Beginner => Difficulty::Intermediate,
+        Difficulty::Intermediate => Difficulty::Expert,
+        Difficulty::Expert | Difficulty::Custom { .. } => Difficulty::Beginner,
+    };
+}
+
+fn reset_timer(mut elapsed: ResMut<ElapsedTime>) {
+    elapsed.0.reset();
+    elapsed.0.pause();
+}
+
+fn stop_timer(mut elapsed: ResMut<ElapsedTime>) {
+    elapsed.0.pause();
+}
+
+fn tick_timer(time: Res<Time>, mut elapsed: ResMut<ElapsedTime>) {
+    elapsed.0.tick(time.delta());
+}
+
+fn update_timer_text(elapsed: Res<ElapsedTime>, mut query: Query<&mut Text, With<TimerText>>) {
+    for mut text in &mut query {
+        *text = Text::new(format!("{:.1}s", elapsed.0.elapsed_secs()));
+    }
+}
+
+fn update_best_time_text(
+    difficulty: Res<Difficulty>,
+    scoreboard: Res<Scoreboard>,
+    mut query: Query<&mut Text, With<BestTimeText>>,
+) {
+    let best = scoreboard.0.get(&*difficulty).copied();
+    for mut text in &mut query {
+        *text = Text::new(match best {
+            Some(seconds) => format!("best {seconds:.1}s"),
+            None => "best --.-s".to_string(),
+        });
+    }
+}
+
+fn record_score(
+    difficulty: Res<Difficulty>,
+    elapsed: Res<ElapsedTime>,
+    mut scoreboard: ResMut<Scoreboard>,
+) {
+    let finished = elapsed.0.elapsed_secs();
+    let best = scoreboard.0.entry(*difficulty).or_insert(f32::MAX);
+    if finished < *best {
+        *best = finished;
+    }
+}
+
+fn spawn_banner(command: &mut Commands, label: &str, color: Color) {
+    command.spawn((
+        Text::new(label),
+        TextFont {
+            font_size: 48.0,
+            ..Default::default()
+        },
+        TextColor(color),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(42.0),
+            left: Val::Percent(50.0),
+            ..Default::default()
+        },
+        Banner,
+    ));
+}
+
+fn won_banner(mut command: Commands) {
+    spawn_banner(
+        &mut command,
+        "You win! Press space to play again",
+        Color::from(GREEN),
+    );
+}
+
+fn lost_banner(mut command: Commands) {
+    spawn_banner(
+        &mut command,
+        "Game over! Press space to play again",
+        Color::from(RED),
+    );
+}
+
+fn despawn_banner(mut command: Commands, query: Query<Entity, With<Banner>>) {
+    for entity in &query {
+        command.entity(entity).despawn();
+    }
+}
+
+// sized from the `Board` that `prepare` just built, not `Difficulty` directly,
+// so a restored save whose dimensions differ from the current `Difficulty`
+// still gets a window that actually fits it
+fn layout(board: Res<Board>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    let (width, height) = screen_size(board.columns, board.rows);
+    for mut window in &mut windows {
+        window.resolution.set(width, height);
+    }
+}
+
+fn load_save(mut command: Commands) {
+    command.insert_resource(SavedGame(std::fs::read_to_string(SAVE_PATH).ok()));
+}
+
+fn load_scoreboard(mut command: Commands) {
+    let scoreboard = std::fs::read_to_string(SCOREBOARD_PATH)
+        .ok()
+        .map(|content| decode_scoreboard(&content))
+        .unwrap_or_default();
+    command.insert_resource(scoreboard);
+}
+
+fn save_on_exit(
+    mut exits: EventReader<AppExit>,
+    board: Res<Board>,
+    query: Query<(&Cell, Option<&Covered>, Option<&Flagged>)>,
+    scoreboard: Res<Scoreboard>,
+) {
+    if exits.read().next().is_none() {
+        return;
+    }
+    let mut states = vec![(false, false); board.grids.len()];
+    for (cell, covered, flagged) in &query {
+        let idx = (cell.y * board.columns + cell.x) as usize;
+        states[idx] = (covered.is_some(), flagged.is_some());
+    }
+    if let Err(err) = std::fs::write(SAVE_PATH, encode_save(&board, &states)) {
+        warn!("failed to save board to {SAVE_PATH}: {err}");
+    }
+    if let Err(err) = std::fs::write(SCOREBOARD_PATH, encode_scoreboard(&scoreboard)) {
+        warn!("failed to save scoreboard to {SCOREBOARD_PATH}: {err}");
+    }
+}
+
 fn prepare(
     mut command: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     materials: Res<Materials>,
+    difficulty: Res<Difficulty>,
+    mut saved: ResMut<SavedGame>,
+    mut elapsed: ResMut<ElapsedTime>,
     mut state: ResMut<NextState<GameState>>,
 ) {
     let mesh = meshes.add(Rectangle::from_length(UNIT));
-    let material = materials.covered.clone();
-    // generate a new board
-    let board = Board::new(X, Y, BOMBS);
-    board.iter().for_each(|grid| {
-        let x = (grid.x - board.columns / 2) as f32 * (UNIT + GAP) + UNIT / 2.0;
-        let y = (grid.y - board.rows / 2) as f32 * (UNIT + GAP) + UNIT / 2.0;
-        command
-            .spawn((
+
+    // a save is only ever restored once; after that, new games follow `Difficulty`
+    let loaded = saved.0.take().as_deref().and_then(decode_save);
+    // a save taken before the first uncover (e.g. quitting during `Prepare`)
+    // has every cell's bomb bit cleared, so derive `bombs_placed` from the
+    // decoded cells instead of assuming every successful load already has
+    // bombs scattered - otherwise `ensure_bombs_placed` would never run and
+    // the restored board could never be won or lost
+    let bombs_placed = loaded
+        .as_ref()
+        .is_some_and(|(board, _)| board.grids.iter().any(|cell| cell.is_bomb));
+    if bombs_placed {
+        // `reset_timer` paused the stopwatch for every `Prepare`, but a
+        // resumed save with bombs already scattered is already running, not
+        // freshly started, so pick the timer back up here instead of
+        // waiting on `ensure_bombs_placed` (which never runs again for an
+        // already-placed board, leaving it paused at 0 forever)
+        elapsed.0.unpause();
+    }
+    let (board, states) = loaded.unwrap_or_else(|| {
+        let board = Board::new(difficulty.columns(), difficulty.rows(), difficulty.bombs());
+        let states = vec![(true, false); board.grids.len()];
+        (board, states)
+    });
+    command.insert_resource(board.clone());
+    command.insert_resource(BombsPlaced(bombs_placed));
+
+    board
+        .iter()
+        .zip(states)
+        .for_each(|(grid, (covered, flagged))| {
+            let x = (grid.x - board.columns / 2) as f32 * (UNIT + GAP) + UNIT / 2.0;
+            let y = (grid.y - board.rows / 2) as f32 * (UNIT + GAP) + UNIT / 2.0;
+            let revealed_count =
+                (!covered && !grid.is_bomb).then(|| adjacent_bomb_count(&board, grid.x, grid.y));
+            let material = if flagged {
+                materials.flagged.clone()
+            } else if !covered {
+                if grid.is_bomb {
+                    materials.bomb.clone()
+                } else {
+                    materials.count[revealed_count.unwrap()].clone()
+                }
+            } else {
+                materials.covered.clone()
+            };
+            let mut entity = command.spawn((
                 #[cfg(feature = "debug")]
                 Name::new("Cell"),
                 grid,
-                Covered,
                 Transform::from_xyz(x, y, 0.0),
                 Visibility::Visible,
                 Mesh2d(mesh.clone()),
-                MeshMaterial2d(material.clone()),
+                MeshMaterial2d(material),
                 Pickable::default(),
-            ))
-            .observe(hovered)
-            .observe(unhover)
-            .observe(interact)
-            .observe(on_uncover);
-    });
+            ));
+            if covered {
+                entity.insert(Covered);
+            }
+            if flagged {
+                entity.insert(Flagged);
+            }
+            if let Some(count) = revealed_count {
+                entity.insert(Count(count as u8));
+                if count > 0 {
+                    spawn_digit(&mut entity, count, &materials);
+                }
+            }
+            entity
+                .observe(hovered)
+                .observe(unhover)
+                .observe(interact)
+                .observe(on_pressed)
+                .observe(on_released)
+                .observe(on_uncover);
+        });
 
     // set next state as running, is there order any problem?
     state.set(GameState::Running);
@@ -310,6 +1185,32 @@ fn cleanup(mut command: Commands, query: Query<Entity, With<Cell>>) {
     }
 }
 
+// how a pointer press is interpreted: desktop mice bind uncover/flag to
+// distinct buttons, while touch input only has one, so we fall back to
+// press duration instead
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub enum InputMode {
+    #[default]
+    MouseButtons,
+    TouchHold,
+}
+
+// holding a cell for at least this long counts as a flag, anything shorter
+// is an uncover
+const TOUCH_HOLD_THRESHOLD: Duration = Duration::from_millis(400);
+
+// tracks when each pointer went down, so `on_released` can measure how long
+// it was held; keyed by pointer id to support more than one touch at once
+#[derive(Clone, Debug, Default, Resource)]
+struct PressTimestamps(HashMap<PointerId, Duration>);
+
+fn toggle_input_mode(mut mode: ResMut<InputMode>) {
+    *mode = match *mode {
+        InputMode::MouseButtons => InputMode::TouchHold,
+        InputMode::TouchHold => InputMode::MouseButtons,
+    };
+}
+
 fn hovered(
     over: Trigger<Pointer<Over>>,
     // we only activate hover effects on covered cells
@@ -344,11 +1245,13 @@ fn unhover(
 
 fn interact(
     click: Trigger<Pointer<Click>>,
+    mode: Res<InputMode>,
     mut interation: InterationParam,
     state: Res<State<GameState>>,
 ) {
-    if !state.is_running() {
-        // disable uncover or flag when not running
+    if !state.is_running() || *mode != InputMode::MouseButtons {
+        // disable uncover or flag when not running, and leave clicks to
+        // `on_released` while we're in touch mode
         return;
     }
     let target = click.target();
@@ -365,6 +1268,49 @@ fn interact(
     }
 }
 
+fn on_pressed(
+    press: Trigger<Pointer<Pressed>>,
+    mode: Res<InputMode>,
+    time: Res<Time>,
+    mut timestamps: ResMut<PressTimestamps>,
+) {
+    if *mode != InputMode::TouchHold {
+        return;
+    }
+    timestamps.0.insert(press.pointer_id, time.elapsed());
+}
+
+// true if a pointer held from `pressed_at` to `released_at` counts as a
+// long press (flag) rather than a quick tap (uncover); pulled out as a pure
+// function so the threshold comparison is testable without a live `World`
+fn is_long_press(pressed_at: Duration, released_at: Duration) -> bool {
+    released_at.saturating_sub(pressed_at) >= TOUCH_HOLD_THRESHOLD
+}
+
+fn on_released(
+    release: Trigger<Pointer<Released>>,
+    mode: Res<InputMode>,
+    time: Res<Time>,
+    mut timestamps: ResMut<PressTimestamps>,
+    mut interation: InterationParam,
+    state: Res<State<GameState>>,
+) {
+    if *mode != InputMode::TouchHold || !state.is_running() {
+        return;
+    }
+    let Some(pressed_at) = timestamps.0.remove(&release.pointer_id) else {
+        return;
+    };
+    let target = release.target();
+    if is_long_press(pressed_at, time.elapsed()) {
+        // held past the threshold: treat it as a flag toggle
+        interation.toggle_flag(target);
+    } else {
+        // quick tap: uncover
+        interation.uncover(target);
+    }
+}
+
 fn on_uncover(
     trigger: Trigger<OnRemove, Covered>,
     mut interation: InterationParam,
@@ -373,8 +1319,8 @@ fn on_uncover(
 ) {
     let target = trigger.target();
     if interation.on_uncover(target) && state.is_running() {
-        // only set if we are not already GameState::Over
-        next.set(GameState::Over);
+        // only set if we are not already game over
+        next.set(GameState::Lost);
     }
 }
 
@@ -393,17 +1339,303 @@ impl Plugin for MineSweeper {
     fn build(&self, app: &mut App) {
         app.add_plugins(MeshPickingPlugin)
             .init_resource::<Materials>()
+            .init_resource::<Difficulty>()
+            .init_resource::<AutoPlay>()
+            .init_resource::<AutoPlayTimer>()
+            .init_resource::<InputMode>()
+            .init_resource::<PressTimestamps>()
+            .init_resource::<ElapsedTime>()
+            .init_resource::<Scoreboard>()
             .init_state::<GameState>()
-            .add_systems(Startup, setup)
+            .add_systems(Startup, (load_save, load_scoreboard, setup))
             .add_systems(FixedUpdate, success.run_if(in_state(GameState::Running)))
-            .add_systems(OnEnter(GameState::Prepare), prepare)
+            .add_systems(
+                OnEnter(GameState::Prepare),
+                (reset_timer, prepare, layout).chain(),
+            )
             .add_systems(
                 Update,
-                restart
-                    .run_if(in_state(GameState::Over))
-                    .run_if(input_just_released(KeyCode::Space)),
+                (
+                    restart
+                        .run_if(game_over)
+                        .run_if(input_just_released(KeyCode::Space)),
+                    cycle_difficulty
+                        .run_if(game_over)
+                        .run_if(input_just_released(KeyCode::KeyD)),
+                    hint.run_if(in_state(GameState::Running))
+                        .run_if(input_just_released(KeyCode::KeyH)),
+                    toggle_autoplay
+                        .run_if(in_state(GameState::Running))
+                        .run_if(input_just_released(KeyCode::KeyP)),
+                    autoplay.run_if(in_state(GameState::Running)),
+                    toggle_input_mode.run_if(input_just_released(KeyCode::KeyT)),
+                    tick_timer,
+                    update_timer_text,
+                    update_best_time_text,
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::Won),
+                (stop_timer, record_score, won_banner, reveal_bombs),
             )
-            .add_systems(OnEnter(GameState::Over), reveal_bombs)
-            .add_systems(OnExit(GameState::Over), cleanup);
+            .add_systems(
+                OnEnter(GameState::Lost),
+                (stop_timer, lost_banner, reveal_bombs),
+            )
+            .add_systems(OnExit(GameState::Won), (despawn_banner, cleanup))
+            .add_systems(OnExit(GameState::Lost), (despawn_banner, cleanup))
+            .add_systems(Last, save_on_exit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_encoding_round_trips_through_all_eight_states() {
+        for state in 0..8u8 {
+            let encoded = encode_cell(state, 0);
+            assert_eq!(decode_cell(encoded, 0), Some(state));
+        }
+    }
+
+    #[test]
+    fn decode_cell_rejects_characters_outside_the_alphabet() {
+        assert_eq!(decode_cell('0', 0), None);
+    }
+
+    #[test]
+    fn decode_cell_rejects_shifts_that_land_outside_the_valid_state_range() {
+        // 'A'..='Z' covers states 0..=25, but only 0..=7 are valid cell states
+        assert_eq!(decode_cell('I', 0), None);
+    }
+
+    #[test]
+    fn save_round_trips_a_board_with_every_kind_of_cell() {
+        let board = Board {
+            columns: 2,
+            rows: 2,
+            bombs: 1,
+            grids: vec![
+                Cell {
+                    x: 0,
+                    y: 0,
+                    is_bomb: true,
+                },
+                Cell {
+                    x: 1,
+                    y: 0,
+                    is_bomb: false,
+                },
+                Cell {
+                    x: 0,
+                    y: 1,
+                    is_bomb: false,
+                },
+                Cell {
+                    x: 1,
+                    y: 1,
+                    is_bomb: false,
+                },
+            ],
+        };
+        let states = vec![(true, true), (true, false), (false, false), (false, true)];
+
+        let saved = encode_save(&board, &states);
+        let (decoded_board, decoded_states) = decode_save(&saved).expect("valid save decodes");
+
+        assert_eq!(decoded_board.columns, board.columns);
+        assert_eq!(decoded_board.rows, board.rows);
+        assert_eq!(decoded_board.bombs, board.bombs);
+        assert_eq!(
+            decoded_board
+                .grids
+                .iter()
+                .map(|cell| cell.is_bomb)
+                .collect::<Vec<_>>(),
+            board
+                .grids
+                .iter()
+                .map(|cell| cell.is_bomb)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(decoded_states, states);
+    }
+
+    #[test]
+    fn decode_save_rejects_a_grid_with_the_wrong_cell_count() {
+        assert!(decode_save("2,2,1\nAAA").is_none());
+    }
+
+    #[test]
+    fn scoreboard_round_trips_every_difficulty_including_custom() {
+        let mut scoreboard = HashMap::new();
+        scoreboard.insert(Difficulty::Beginner, 12.5);
+        scoreboard.insert(Difficulty::Intermediate, 64.0);
+        scoreboard.insert(Difficulty::Expert, 180.25);
+        scoreboard.insert(
+            Difficulty::Custom {
+                columns: 5,
+                rows: 6,
+                bombs: 7,
+            },
+            9.0,
+        );
+        let scoreboard = Scoreboard(scoreboard);
+
+        let encoded = encode_scoreboard(&scoreboard);
+        let decoded = decode_scoreboard(&encoded);
+
+        assert_eq!(decoded.0, scoreboard.0);
+    }
+
+    #[test]
+    fn decode_scoreboard_skips_unparseable_lines() {
+        let decoded = decode_scoreboard("beginner,12.5\nnot a line\nexpert,not-a-number");
+        assert_eq!(decoded.0.get(&Difficulty::Beginner), Some(&12.5));
+        assert_eq!(decoded.0.len(), 1);
+    }
+
+    #[test]
+    fn consistent_rejects_an_assignment_that_overshoots_its_constraint() {
+        // constraint: exactly 1 bomb among cells [0, 1], but both assigned true
+        let constraints = [Constraint {
+            cells: vec![0, 1],
+            required: 1,
+        }];
+        let assigned = [true, true];
+        let values = [true, true];
+        assert!(!consistent(&constraints, &assigned, &values));
+    }
+
+    #[test]
+    fn consistent_rejects_an_assignment_that_cannot_reach_its_constraint() {
+        // constraint: exactly 2 bombs among cells [0, 1], but both already false
+        let constraints = [Constraint {
+            cells: vec![0, 1],
+            required: 2,
+        }];
+        let assigned = [true, true];
+        let values = [false, false];
+        assert!(!consistent(&constraints, &assigned, &values));
+    }
+
+    #[test]
+    fn consistent_allows_a_partial_assignment_that_can_still_go_either_way() {
+        // constraint: exactly 1 bomb among cells [0, 1], only cell 0 assigned so far
+        let constraints = [Constraint {
+            cells: vec![0, 1],
+            required: 1,
+        }];
+        let assigned = [true, false];
+        let values = [false, false];
+        assert!(consistent(&constraints, &assigned, &values));
+    }
+
+    #[test]
+    fn solve_gives_a_forced_bomb_probability_one() {
+        // a single revealed "1" with exactly one covered neighbor: that
+        // neighbor must be a bomb
+        let frontier = vec![Entity::PLACEHOLDER];
+        let constraints = [Constraint {
+            cells: vec![0],
+            required: 1,
+        }];
+        let (probs, _) = solve(&frontier, &constraints, 0, 1);
+        assert_eq!(probs, vec![1.0]);
+    }
+
+    #[test]
+    fn solve_gives_a_forced_safe_cell_probability_zero() {
+        // a revealed "0" has no bombs among its covered neighbor
+        let frontier = vec![Entity::PLACEHOLDER];
+        let constraints = [Constraint {
+            cells: vec![0],
+            required: 0,
+        }];
+        let (probs, _) = solve(&frontier, &constraints, 0, 0);
+        assert_eq!(probs, vec![0.0]);
+    }
+
+    #[test]
+    fn solve_splits_probability_evenly_between_two_equally_likely_cells() {
+        // exactly 1 bomb shared between two indistinguishable covered cells
+        let frontier = vec![Entity::PLACEHOLDER, Entity::PLACEHOLDER];
+        let constraints = [Constraint {
+            cells: vec![0, 1],
+            required: 1,
+        }];
+        let (probs, _) = solve(&frontier, &constraints, 0, 1);
+        assert_eq!(probs, vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn select_bomb_entities_never_picks_the_clicked_cell_or_its_neighbors() {
+        // a 5x5 board: the center is clicked, so only the 16 cells of the outer
+        // ring lie outside its 3x3 block and are eligible
+        let candidates: Vec<(Entity, i32, i32)> = (0..5)
+            .flat_map(|y| (0..5).map(move |x| (x, y)))
+            .enumerate()
+            .map(|(idx, (x, y))| (Entity::from_raw(idx as u32), x, y))
+            .collect();
+
+        let bombs = select_bomb_entities(&candidates, (2, 2), 16);
+
+        assert_eq!(bombs.len(), 16);
+        let ring: Vec<Entity> = candidates
+            .iter()
+            .filter(|&&(_, x, y)| x == 0 || x == 4 || y == 0 || y == 4)
+            .map(|&(entity, _, _)| entity)
+            .collect();
+        for entity in &bombs {
+            assert!(ring.contains(entity));
+        }
+    }
+
+    #[test]
+    fn select_bomb_entities_returns_distinct_entities() {
+        let candidates: Vec<(Entity, i32, i32)> = (0..10u32)
+            .map(|idx| (Entity::from_raw(idx), 10 + idx as i32, 10 + idx as i32))
+            .collect();
+
+        let bombs = select_bomb_entities(&candidates, (0, 0), 5);
+
+        assert_eq!(bombs.len(), 5);
+        let mut unique = bombs.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), bombs.len());
+    }
+
+    #[test]
+    fn select_bomb_entities_clamps_to_the_number_of_eligible_cells() {
+        // clicked cell excludes itself, leaving only 1 eligible candidate
+        let candidates = vec![(Entity::from_raw(0), 0, 0), (Entity::from_raw(1), 5, 5)];
+
+        let bombs = select_bomb_entities(&candidates, (0, 0), 10);
+
+        assert_eq!(bombs, vec![Entity::from_raw(1)]);
+    }
+
+    #[test]
+    fn is_long_press_is_false_for_a_quick_tap() {
+        let pressed_at = Duration::from_millis(100);
+        let released_at = pressed_at + Duration::from_millis(100);
+        assert!(!is_long_press(pressed_at, released_at));
+    }
+
+    #[test]
+    fn is_long_press_is_true_once_the_hold_reaches_the_threshold() {
+        let pressed_at = Duration::from_millis(100);
+        let released_at = pressed_at + TOUCH_HOLD_THRESHOLD;
+        assert!(is_long_press(pressed_at, released_at));
+    }
+
+    #[test]
+    fn is_long_press_is_true_for_a_long_hold() {
+        let pressed_at = Duration::from_millis(100);
+        let released_at = pressed_at + TOUCH_HOLD_THRESHOLD + Duration::from_secs(1);
+        assert!(is_long_press(pressed_at, released_at));
     }
 }